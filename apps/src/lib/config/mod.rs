@@ -0,0 +1,88 @@
+//! Node configuration.
+
+use namada::types::key::common;
+use serde::{Deserialize, Serialize};
+
+use crate::facade::tendermint_config::TendermintConfig;
+
+/// Ledger (node) configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Ledger {
+    /// Namada-side shell configuration.
+    pub shell: Shell,
+    /// The underlying CometBFT configuration.
+    pub cometbft: TendermintConfig,
+}
+
+/// The mode a Tendermint/CometBFT node runs in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TendermintMode {
+    /// A full node that does not participate in consensus.
+    Full,
+    /// A validator node.
+    Validator,
+    /// A seed node that only serves peer discovery.
+    Seed,
+}
+
+impl TendermintMode {
+    /// The string CometBFT expects for this mode on the `init` command line.
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            TendermintMode::Full => "full",
+            TendermintMode::Validator => "validator",
+            TendermintMode::Seed => "seed",
+        }
+    }
+}
+
+/// Namada-side (shell) configuration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Shell {
+    /// Which mode to run the CometBFT node in.
+    pub tendermint_mode: TendermintMode,
+    /// Listen address for a remote (tmkms-style) consensus signer. When set,
+    /// CometBFT delegates signing to an external key-management process over
+    /// this socket instead of reading `priv_validator_key.json`, so the
+    /// consensus key never has to live on the node's filesystem.
+    #[serde(default)]
+    pub validator_signer_laddr: Option<String>,
+    /// An optional Namada-managed key used to pin a stable P2P node identity.
+    /// When set, it is serialized into `config/node_key.json`, overriding the
+    /// random key CometBFT's `init` generates, so the node ID survives a
+    /// `reset` and can be used in persistent-peer and seed entries.
+    #[serde(default)]
+    pub node_key: Option<common::SecretKey>,
+    /// CometBFT state sync bootstrap configuration. When enabled, a fresh node
+    /// bootstraps from a recent snapshot instead of replaying all blocks.
+    #[serde(default)]
+    pub statesync: Option<StateSyncConfig>,
+    /// When set, a watchdog shuts the node down if CometBFT produces no new
+    /// block within this many seconds, giving operators automated detection of
+    /// a wedged instance. Monitoring is disabled when unset.
+    #[serde(default)]
+    pub block_watchdog_secs: Option<u64>,
+}
+
+/// CometBFT state sync configuration. Mirrors the `[statesync]` section of
+/// `config.toml`. The ABCI app (Namada) must also advertise snapshots through
+/// its `ListSnapshots`/`OfferSnapshot` handlers for this to take effect.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StateSyncConfig {
+    /// Whether state sync is enabled.
+    pub enable: bool,
+    /// RPC servers to fetch snapshots and verify the light-client header
+    /// against. CometBFT requires at least two for verification.
+    #[serde(default)]
+    pub rpc_servers: Vec<String>,
+    /// A trusted block height to anchor light-client verification.
+    #[serde(default)]
+    pub trust_height: u64,
+    /// The block hash at `trust_height`, hex-encoded.
+    #[serde(default)]
+    pub trust_hash: String,
+    /// How long headers are trusted for, e.g. `"168h0m0s"`.
+    #[serde(default)]
+    pub trust_period: String,
+}
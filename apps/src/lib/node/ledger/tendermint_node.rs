@@ -2,8 +2,10 @@ use std::env;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::str::FromStr;
+use std::time::Duration;
 
 use borsh_ext::BorshSerializeExt;
+use futures::StreamExt;
 use namada::types::chain::ChainId;
 use namada::types::key::*;
 use namada::types::storage::BlockHeight;
@@ -44,6 +46,12 @@ pub enum Error {
     Runtime(String),
     #[error("Failed to rollback CometBFT state: {0}")]
     RollBack(String),
+    #[error("Unsupported or unrecognized CometBFT version: {0}")]
+    Version(String),
+    #[error("Invalid state sync configuration: {0}")]
+    StateSync(String),
+    #[error("Failed to monitor CometBFT node: {0}")]
+    Monitor(String),
     #[error("Failed to convert to String: {0:?}")]
     TendermintPath(std::ffi::OsString),
 }
@@ -67,6 +75,222 @@ fn from_env_or_default() -> Result<String> {
     }
 }
 
+/// Supported CometBFT/Tendermint protocol generations. The shapes of
+/// `config.toml` and `genesis.json` (notably the ABCI and consensus-params
+/// schemas) changed across these releases, so config/genesis emission has to
+/// branch on which one the operator's binary speaks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CometbftVersion {
+    /// Tendermint Core 0.34.x
+    V0_34,
+    /// CometBFT 0.37.x
+    V0_37,
+    /// CometBFT 0.38.x
+    V0_38,
+}
+
+impl CometbftVersion {
+    /// Map the `major.minor` of a reported semver onto a supported
+    /// generation, returning [`Error::Version`] when it is not one of the
+    /// versions we know how to configure.
+    fn from_semver(reported: &str) -> Result<Self> {
+        // `cometbft version` may print extra banner text, so pick the first
+        // token that looks like a semver.
+        let semver = reported
+            .split_ascii_whitespace()
+            .find(|tok| {
+                let t = tok.trim_start_matches('v');
+                t.split('.').count() >= 2
+                    && t.starts_with(|c: char| c.is_ascii_digit())
+            })
+            .ok_or_else(|| {
+                Error::Version(format!(
+                    "Could not parse a CometBFT version out of {reported:?}"
+                ))
+            })?;
+        let mut parts = semver.trim_start_matches('v').split('.');
+        let major = leading_u64(parts.next());
+        let minor = leading_u64(parts.next());
+        match (major, minor) {
+            (Some(0), Some(34)) => Ok(Self::V0_34),
+            (Some(0), Some(37)) => Ok(Self::V0_37),
+            (Some(0), Some(38)) => Ok(Self::V0_38),
+            (Some(major), Some(minor)) => Err(Error::Version(format!(
+                "Unsupported CometBFT version {major}.{minor}; the supported \
+                 versions are 0.34, 0.37 and 0.38"
+            ))),
+            _ => Err(Error::Version(format!(
+                "Could not parse a CometBFT version out of {reported:?}"
+            ))),
+        }
+    }
+}
+
+/// Parse the leading run of ASCII digits of a version component, tolerating
+/// pre-release suffixes such as `38-rc1`.
+fn leading_u64(part: Option<&str>) -> Option<u64> {
+    let digits: String = part?
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+/// Detect the version of the CometBFT binary by running `cometbft version`
+/// and parsing the reported semver.
+async fn cometbft_version(tendermint_path: &str) -> Result<CometbftVersion> {
+    let output = Command::new(tendermint_path)
+        .args(["version"])
+        .output()
+        .await
+        .map_err(|e| Error::Version(e.to_string()))?;
+    let reported = String::from_utf8(output.stdout)
+        .map_err(|e| Error::Version(e.to_string()))?;
+    let version = CometbftVersion::from_semver(reported.trim())?;
+    tracing::info!("Detected CometBFT version: {:?}", version);
+    Ok(version)
+}
+
+/// How often `/status` is polled while following the node, so a stalled
+/// consensus is noticed even when no events arrive over the websocket.
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A snapshot of the node's liveness, surfaced to callers through the monitor
+/// channel so operators can observe whether the node is syncing or wedged.
+#[derive(Clone, Debug)]
+pub struct NodeStatus {
+    /// Latest block height the node has seen.
+    pub latest_height: BlockHeight,
+    /// Whether the node is still catching up with its peers.
+    pub catching_up: bool,
+}
+
+/// Derive the websocket subscription URL (`ws://host:port/websocket`) from an
+/// RPC listen address such as `tcp://0.0.0.0:26657`. A wildcard bind address
+/// is rewritten to the loopback interface for the local connection.
+fn rpc_to_ws_url(rpc_laddr: &str) -> String {
+    let host = rpc_laddr
+        .trim_start_matches("tcp://")
+        .trim_start_matches("http://")
+        .trim_start_matches("https://")
+        .trim_end_matches('/')
+        .replace("0.0.0.0", "127.0.0.1");
+    format!("ws://{host}/websocket")
+}
+
+/// Follow the node's local RPC after startup: subscribe to `NewBlock` events
+/// over the websocket and periodically poll `/status`, reporting the latest
+/// height and catching-up flag through `status_tx`. Returns
+/// [`Error::Monitor`] when no new block is seen within `watchdog`, so the
+/// caller can log a warning and/or shut the wedged node down.
+async fn monitor_node(
+    rpc_laddr: String,
+    watchdog: Duration,
+    status_tx: tokio::sync::watch::Sender<Option<NodeStatus>>,
+) -> Result<()> {
+    use crate::facade::tendermint_rpc::query::EventType;
+    use crate::facade::tendermint_rpc::{
+        Client, SubscriptionClient, WebSocketClient,
+    };
+
+    let ws_url = rpc_to_ws_url(&rpc_laddr);
+
+    // The RPC server is not up the instant the child is spawned, so retry the
+    // initial connection for a short while before giving up.
+    let (client, driver) = {
+        let mut attempt = 0;
+        loop {
+            match WebSocketClient::new(ws_url.as_str()).await {
+                Ok(pair) => break pair,
+                Err(err) if attempt < 10 => {
+                    attempt += 1;
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    tracing::debug!(
+                        "Waiting for CometBFT RPC at {ws_url} ({err})"
+                    );
+                }
+                Err(err) => return Err(Error::Monitor(err.to_string())),
+            }
+        }
+    };
+    let driver_handle = tokio::spawn(driver.run());
+    let mut subscription = client
+        .subscribe(EventType::NewBlock.into())
+        .await
+        .map_err(|e| Error::Monitor(e.to_string()))?;
+
+    let mut last_block = tokio::time::Instant::now();
+    let mut last_height = BlockHeight::default();
+    // Assume the node is catching up until `/status` says otherwise, so the
+    // watchdog doesn't fire during the initial sync.
+    let mut catching_up = true;
+    let mut ticker = tokio::time::interval(STATUS_POLL_INTERVAL);
+    // The first tick fires immediately; skip it so we poll on a cadence.
+    ticker.tick().await;
+
+    let result = loop {
+        tokio::select! {
+            event = subscription.next() => {
+                match event {
+                    Some(Ok(_)) => {
+                        last_block = tokio::time::Instant::now();
+                    }
+                    Some(Err(err)) => break Err(Error::Monitor(err.to_string())),
+                    None => break Ok(()),
+                }
+            }
+            _ = ticker.tick() => {
+                match client.status().await {
+                    Ok(status) => {
+                        let latest_height = BlockHeight(
+                            status.sync_info.latest_block_height.value(),
+                        );
+                        catching_up = status.sync_info.catching_up;
+                        // Treat an advancing height as liveness too, so a
+                        // dropped or wedged subscription doesn't false-trigger
+                        // the watchdog against an otherwise healthy node.
+                        if latest_height > last_height {
+                            last_height = latest_height;
+                            last_block = tokio::time::Instant::now();
+                        }
+                        // Ignore send errors: no receiver just means nobody is
+                        // observing the status right now.
+                        let _ = status_tx.send(Some(NodeStatus {
+                            latest_height,
+                            catching_up,
+                        }));
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed to poll CometBFT /status: {err}");
+                    }
+                }
+            }
+            // The watchdog deadline is independent of the poll cadence, so a
+            // sub-poll-interval `block_watchdog_secs` is honored exactly.
+            _ = tokio::time::sleep_until(last_block + watchdog) => {
+                if catching_up {
+                    // Height legitimately isn't advancing yet; don't kill the
+                    // node. Reset the deadline so we don't spin until it syncs.
+                    last_block = tokio::time::Instant::now();
+                } else {
+                    let secs = watchdog.as_secs();
+                    tracing::warn!(
+                        "CometBFT produced no new block for {secs}s; the node \
+                         may be stalled"
+                    );
+                    break Err(Error::Monitor(format!(
+                        "no new block within {secs}s watchdog"
+                    )));
+                }
+            }
+        }
+    };
+
+    let _ = client.close();
+    let _ = driver_handle.await;
+    result
+}
+
 /// Run the tendermint node.
 pub async fn run(
     home_dir: PathBuf,
@@ -82,6 +306,11 @@ pub async fn run(
     let tendermint_path = from_env_or_default()?;
     let mode = config.shell.tendermint_mode.to_str().to_owned();
 
+    // Detect the binary's version up front so config and genesis are emitted
+    // in the schema it expects, rather than letting it fail obscurely at
+    // startup on an unrecognized field.
+    let version = cometbft_version(&tendermint_path).await?;
+
     // init and run a tendermint node child process
     let output = Command::new(&tendermint_path)
         .args(["init", &mode, "--home", &home_dir_string])
@@ -92,9 +321,42 @@ pub async fn run(
         panic!("Tendermint failed to initialize with {:#?}", output);
     }
 
-    write_tm_genesis(&home_dir, chain_id, genesis_time).await;
+    write_tm_genesis(&home_dir, chain_id, genesis_time, version).await;
+
+    // When a remote signer listen address is configured, CometBFT delegates
+    // consensus signing to an external key-management process over this
+    // socket, so the raw key never has to be written to disk.
+    let priv_validator_laddr =
+        config.shell.validator_signer_laddr.clone();
+    // Capture the RPC listen address before the cometbft config is moved, so
+    // the monitor can follow the node over its local websocket.
+    let rpc_laddr = config.cometbft.rpc.laddr.to_string();
+    let block_watchdog =
+        config.shell.block_watchdog_secs.map(Duration::from_secs);
+
+    let statesync = config.shell.statesync.clone();
+    update_tendermint_config(
+        &home_dir,
+        config.cometbft,
+        priv_validator_laddr,
+        statesync,
+        version,
+    )
+    .await?;
 
-    update_tendermint_config(&home_dir, config.cometbft).await?;
+    // Pin the P2P node identity to a Namada-managed key when one is
+    // configured, overwriting the random `node_key.json` produced by `init`.
+    if let Some(node_key) = config.shell.node_key.as_ref() {
+        write_node_key_async(&home_dir, node_key).await;
+    }
+
+    // With a remote signer configured, make sure the raw consensus key is not
+    // left on disk: both `init` above and the node setup path write a
+    // `priv_validator_key.json`, so delete it and let CometBFT sign over the
+    // socket instead.
+    if config.shell.validator_signer_laddr.is_some() {
+        remove_validator_key(&home_dir).await?;
+    }
 
     let mut tendermint_node = Command::new(&tendermint_path);
     tendermint_node.args([
@@ -119,7 +381,39 @@ pub async fn run(
         .map_err(Error::StartUp)?;
     tracing::info!("CometBFT node started");
 
+    // A `None` watchdog disables monitoring; the branch below is guarded so it
+    // stays pending forever in that case.
+    let (status_tx, mut status_rx) = tokio::sync::watch::channel(None);
+    if block_watchdog.is_some() {
+        // Observe the liveness the monitor reports. Holding the receiver also
+        // keeps the channel open so the monitor's sends aren't dropped.
+        tokio::spawn(async move {
+            while status_rx.changed().await.is_ok() {
+                if let Some(status) = status_rx.borrow_and_update().clone() {
+                    tracing::info!(
+                        "CometBFT at height {} (catching_up: {})",
+                        status.latest_height,
+                        status.catching_up,
+                    );
+                }
+            }
+        });
+    }
+
     tokio::select! {
+        result = monitor_node(
+            rpc_laddr,
+            block_watchdog.unwrap_or_default(),
+            status_tx,
+        ), if block_watchdog.is_some() => {
+            tracing::info!("Shutting down Tendermint node after monitor exit...");
+            if let Err(err) = tendermint_node.kill().await {
+                tracing::error!(
+                    "Failed to kill CometBFT node after monitor exit: {err}"
+                );
+            }
+            result
+        },
         status = tendermint_node.wait() => {
             match status {
                 Ok(status) => {
@@ -172,9 +466,20 @@ pub fn reset(tendermint_dir: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
-pub fn rollback(tendermint_dir: impl AsRef<Path>) -> Result<BlockHeight> {
+/// The outcome of a CometBFT state rollback.
+#[derive(Clone, Debug)]
+pub struct RollbackOutput {
+    /// The block height the node was rolled back to.
+    pub height: BlockHeight,
+    /// The app hash at that height, as reported by CometBFT. Empty when it
+    /// could only be recovered from the node's on-disk state.
+    pub app_hash: String,
+}
+
+pub fn rollback(tendermint_dir: impl AsRef<Path>) -> Result<RollbackOutput> {
     let tendermint_path = from_env_or_default()?;
-    let tendermint_dir = tendermint_dir.as_ref().to_string_lossy();
+    let tendermint_dir = tendermint_dir.as_ref();
+    let tendermint_dir_str = tendermint_dir.to_string_lossy();
 
     // Rollback tendermint state, see https://github.com/tendermint/tendermint/blob/main/cmd/tendermint/commands/rollback.go for details
     // on how the tendermint rollback behaves
@@ -185,69 +490,192 @@ pub fn rollback(tendermint_dir: impl AsRef<Path>) -> Result<BlockHeight> {
             // NOTE: log config: https://docs.tendermint.com/master/nodes/logging.html#configuring-log-levels
             // "--log-level=\"*debug\"",
             "--home",
-            &tendermint_dir,
+            &tendermint_dir_str,
         ])
         .output()
         .map_err(|e| Error::RollBack(e.to_string()))?;
 
-    // Capture the block height from the output of tendermint rollback
-    // Tendermint stdout message: "Rolled
-    // back state to height %d and hash %v"
     let output_msg = String::from_utf8(output.stdout)
         .map_err(|e| Error::RollBack(e.to_string()))?;
-    let (_, right) = output_msg
-        .split_once("Rolled back state to height")
-        .ok_or(Error::RollBack(
-            "Missing expected block height in tendermint stdout message"
-                .to_string(),
-        ))?;
 
-    let mut sub = right.split_ascii_whitespace();
-    let height = sub.next().ok_or(Error::RollBack(
-        "Missing expected block height in tendermint stdout message"
-            .to_string(),
-    ))?;
+    // The stdout message has historically read
+    // "Rolled back state to height %d and hash %v", but the wording changes
+    // across releases. When it parses it is authoritative. When it doesn't, we
+    // attempt a best-effort recovery from the block store (see
+    // `read_block_store_height` — only Tendermint 0.34 stores it as JSON) and
+    // otherwise return `Error::RollBack` so the caller can fall back.
+    match parse_rollback_output(&output_msg) {
+        Some(scraped) => {
+            // Opportunistic cross-check for observability, but never let it
+            // override a successfully-parsed stdout value. This silently no-ops
+            // on 0.37/0.38, whose block-store state is protobuf.
+            if let Some(store_height) =
+                read_block_store_height(tendermint_dir)
+            {
+                if scraped.height != store_height {
+                    tracing::warn!(
+                        "Rollback height from stdout ({}) disagrees with the \
+                         block store height ({}); using the stdout value",
+                        scraped.height,
+                        store_height,
+                    );
+                }
+            }
+            Ok(scraped)
+        }
+        None => {
+            tracing::warn!(
+                "Could not parse CometBFT rollback stdout; attempting to \
+                 recover the height from the block store"
+            );
+            match read_block_store_height(tendermint_dir) {
+                Some(store_height) => Ok(RollbackOutput {
+                    height: store_height,
+                    app_hash: String::new(),
+                }),
+                None => Err(Error::RollBack(format!(
+                    "Unrecognized rollback output and could not recover the \
+                     height from the block store: {output_msg:?}"
+                ))),
+            }
+        }
+    }
+}
 
-    Ok(height
-        .parse::<u64>()
-        .map_err(|e| Error::RollBack(e.to_string()))?
-        .into())
+/// Scrape the rolled-back height and app hash out of CometBFT's rollback
+/// stdout message. Returns `None` when the message does not match the
+/// expected format so the caller can fall back to the on-disk state.
+fn parse_rollback_output(output_msg: &str) -> Option<RollbackOutput> {
+    let (_, right) = output_msg.split_once("Rolled back state to height")?;
+    let height: u64 = right.split_ascii_whitespace().next()?.parse().ok()?;
+    let app_hash = right
+        .split_once("hash")?
+        .1
+        .split_ascii_whitespace()
+        .next()?
+        .to_string();
+    Some(RollbackOutput {
+        height: height.into(),
+        app_hash,
+    })
 }
 
-/// Convert a common signing scheme validator key into JSON for
-/// Tendermint
-fn validator_key_to_json(
-    sk: &common::SecretKey,
-) -> std::result::Result<serde_json::Value, ParseSecretKeyError> {
-    let raw_hash = tm_consensus_key_raw_hash(&sk.ref_to());
-    let (id_str, pk_arr, kp_arr) = match sk {
+/// Read the node's actual post-rollback block height from CometBFT's block
+/// store, used to cross-check (or recover) the rolled-back height independently
+/// of the log wording. This is the real block height, unlike
+/// `priv_validator_state.json`, which only records the validator's last-signed
+/// height for double-sign protection.
+///
+/// NOTE: this is a best-effort, **Tendermint 0.34-only** path. 0.34 persists
+/// the block store state as a JSON value (`{"base":..,"height":..}`) in its
+/// goleveldb block store, which we can scan for without linking a leveldb
+/// reader. CometBFT 0.37/0.38 marshal `BlockStoreState` as protobuf, so this
+/// returns `None` there — the cross-check is skipped and, on an unparseable
+/// stdout, `rollback` surfaces `Error::RollBack` for the caller to handle.
+fn read_block_store_height(tendermint_dir: &Path) -> Option<BlockHeight> {
+    let store_dir = tendermint_dir.join("data").join("blockstore.db");
+    let mut best: Option<u64> = None;
+    for entry in std::fs::read_dir(&store_dir).ok()?.flatten() {
+        let Ok(bytes) = std::fs::read(entry.path()) else {
+            continue;
+        };
+        for height in scan_block_store_heights(&bytes) {
+            best = Some(best.map_or(height, |b| b.max(height)));
+        }
+    }
+    best.map(Into::into)
+}
+
+/// Scan a raw block store file for Tendermint 0.34's JSON-marshaled
+/// `BlockStoreState` blobs and return every `height` found. Newer releases
+/// marshal this state as protobuf and are not handled here.
+fn scan_block_store_heights(bytes: &[u8]) -> Vec<u64> {
+    const NEEDLE: &[u8] = b"{\"base\":";
+    let mut out = Vec::new();
+    let mut offset = 0;
+    while let Some(pos) = find_subslice(&bytes[offset..], NEEDLE) {
+        let start = offset + pos;
+        match bytes[start..].iter().position(|&b| b == b'}') {
+            Some(end_rel) => {
+                let end = start + end_rel + 1;
+                if let Ok(text) = std::str::from_utf8(&bytes[start..end]) {
+                    if let Ok(state) =
+                        serde_json::from_str::<serde_json::Value>(text)
+                    {
+                        if let Some(h) =
+                            state.get("height").and_then(|v| v.as_u64())
+                        {
+                            out.push(h);
+                        }
+                    }
+                }
+                offset = end;
+            }
+            None => break,
+        }
+    }
+    out
+}
+
+/// Find the first occurrence of `needle` in `haystack`.
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Convert the public part of a validator consensus key into the JSON
+/// `{"type":"tendermint/PubKey...","value":base64}` shape that Tendermint
+/// uses both in `priv_validator_key.json` and in the privval protocol's
+/// `PubKeyResponse`. This is also what an operator hands to an out-of-process,
+/// tmkms-style signer (which answers `PubKeyRequest` with it) when the private
+/// key is kept in an HSM/KMS rather than on the node's filesystem.
+pub fn validator_pubkey_to_json(pk: &common::PublicKey) -> serde_json::Value {
+    let (id_str, pk_arr) = match pk {
+        common::PublicKey::Ed25519(pk) => ("Ed25519", pk.serialize_to_vec()),
+        common::PublicKey::Secp256k1(pk) => {
+            ("Secp256k1", pk.serialize_to_vec())
+        }
+    };
+    json!({
+        "type": format!("tendermint/PubKey{}", id_str),
+        "value": base64::encode(pk_arr),
+    })
+}
+
+/// Convert a common signing scheme secret key into Tendermint's
+/// `{"type":"tendermint/PrivKey...","value":base64}` JSON shape. This is
+/// shared by `priv_validator_key.json` and `node_key.json`.
+fn privkey_to_json(sk: &common::SecretKey) -> serde_json::Value {
+    let (id_str, kp_arr) = match sk {
         common::SecretKey::Ed25519(_) => {
             let sk_ed: ed25519::SecretKey = sk.try_to_sk().unwrap();
             let keypair =
                 [sk_ed.serialize_to_vec(), sk_ed.ref_to().serialize_to_vec()]
                     .concat();
-            ("Ed25519", sk_ed.ref_to().serialize_to_vec(), keypair)
+            ("Ed25519", keypair)
         }
         common::SecretKey::Secp256k1(_) => {
             let sk_sec: secp256k1::SecretKey = sk.try_to_sk().unwrap();
-            (
-                "Secp256k1",
-                sk_sec.ref_to().serialize_to_vec(),
-                sk_sec.serialize_to_vec(),
-            )
+            ("Secp256k1", sk_sec.serialize_to_vec())
         }
     };
+    json!({
+        "type": format!("tendermint/PrivKey{}", id_str),
+        "value": base64::encode(kp_arr),
+    })
+}
 
+/// Convert a common signing scheme validator key into JSON for
+/// Tendermint
+fn validator_key_to_json(
+    sk: &common::SecretKey,
+) -> std::result::Result<serde_json::Value, ParseSecretKeyError> {
+    let raw_hash = tm_consensus_key_raw_hash(&sk.ref_to());
     Ok(json!({
         "address": raw_hash,
-        "pub_key": {
-            "type": format!("tendermint/PubKey{}",id_str),
-            "value": base64::encode(pk_arr),
-        },
-        "priv_key": {
-            "type": format!("tendermint/PrivKey{}",id_str),
-            "value": base64::encode(kp_arr),
-        }
+        "pub_key": validator_pubkey_to_json(&sk.ref_to()),
+        "priv_key": privkey_to_json(sk),
     }))
 }
 
@@ -300,6 +728,81 @@ pub fn write_validator_key(
         .expect("Couldn't write private validator key file");
 }
 
+/// Initialize the persistent P2P node key for Tendermint. Pinning the node's
+/// ID to a Namada-managed key (rather than the random one CometBFT's `init`
+/// generates) keeps it stable across a `reset` and makes it usable in
+/// persistent-peer and seed entries.
+pub async fn write_node_key_async(
+    home_dir: impl AsRef<Path>,
+    node_key: &common::SecretKey,
+) {
+    let home_dir = home_dir.as_ref();
+    let path = home_dir.join("config").join("node_key.json");
+    // Make sure the dir exists
+    let config_dir = path.parent().unwrap();
+    fs::create_dir_all(config_dir)
+        .await
+        .expect("Couldn't create node key directory");
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&path)
+        .await
+        .expect("Couldn't create node key file");
+    let key = json!({ "priv_key": privkey_to_json(node_key) });
+    let data = serde_json::to_vec_pretty(&key)
+        .expect("Couldn't encode node key file");
+    file.write_all(&data[..])
+        .await
+        .expect("Couldn't write node key file");
+}
+
+/// Initialize the persistent P2P node key for Tendermint
+pub fn write_node_key(
+    home_dir: impl AsRef<Path>,
+    node_key: &common::SecretKey,
+) {
+    let home_dir = home_dir.as_ref();
+    let path = home_dir.join("config").join("node_key.json");
+    // Make sure the dir exists
+    let config_dir = path.parent().unwrap();
+    std::fs::create_dir_all(config_dir)
+        .expect("Couldn't create node key directory");
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&path)
+        .expect("Couldn't create node key file");
+    let key = json!({ "priv_key": privkey_to_json(node_key) });
+    serde_json::to_writer_pretty(file, &key)
+        .expect("Couldn't write node key file");
+}
+
+/// Remove any `priv_validator_key.json` from disk. Used when consensus signing
+/// is delegated to a remote signer, so the raw key is never left on the node.
+async fn remove_validator_key(home_dir: impl AsRef<Path>) -> Result<()> {
+    let path = home_dir
+        .as_ref()
+        .join("config")
+        .join("priv_validator_key.json");
+    match fs::remove_file(&path).await {
+        Ok(()) => {
+            tracing::info!(
+                "Removed {} in favor of the configured remote signer",
+                path.display()
+            );
+            Ok(())
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(Error::Runtime(format!(
+            "Failed to remove {}: {err}",
+            path.display()
+        ))),
+    }
+}
+
 /// Initialize validator private state for Tendermint
 pub fn write_validator_state(home_dir: impl AsRef<Path>) {
     let home_dir = home_dir.as_ref();
@@ -348,6 +851,9 @@ pub fn id_from_pk(pk: &common::PublicKey) -> TendermintNodeId {
 async fn update_tendermint_config(
     home_dir: impl AsRef<Path>,
     mut config: TendermintConfig,
+    priv_validator_laddr: Option<String>,
+    statesync: Option<config::StateSyncConfig>,
+    version: CometbftVersion,
 ) -> Result<()> {
     let home_dir = home_dir.as_ref();
     let path = home_dir.join("config").join("config.toml");
@@ -358,6 +864,20 @@ async fn update_tendermint_config(
 
     config.consensus.create_empty_blocks = true;
 
+    // Remote (tmkms-style) validator signing. When set, CometBFT listens on
+    // this address for an external signer that speaks the privval protocol
+    // instead of reading `priv_validator_key.json`, keeping the consensus key
+    // off the node's filesystem.
+    if let Some(laddr) = priv_validator_laddr {
+        config.priv_validator_laddr = Some(
+            laddr
+                .parse()
+                .map_err(|e| Error::Runtime(format!(
+                    "Invalid validator signer listen address {laddr:?}: {e}"
+                )))?,
+        );
+    }
+
     // mempool config
     // https://forum.cosmos.network/t/our-understanding-of-the-cosmos-hub-mempool-issues/12040
     {
@@ -397,17 +917,107 @@ async fn update_tendermint_config(
         .open(path)
         .await
         .map_err(Error::OpenWriteConfig)?;
+    // Serialize to a generic TOML value so we can rename/drop sections whose
+    // shape differs from what the typed `TendermintConfig` emits before
+    // handing the file to the detected binary.
+    let mut value = toml::Value::try_from(&config)
+        .map_err(Error::ConfigSerializeToml)?;
+    apply_config_version_fixups(&mut value, version);
+    if let Some(statesync) = statesync {
+        apply_statesync_config(&mut value, &statesync)?;
+    }
     let config_str =
-        toml::to_string(&config).map_err(Error::ConfigSerializeToml)?;
+        toml::to_string(&value).map_err(Error::ConfigSerializeToml)?;
     file.write_all(config_str.as_bytes())
         .await
         .map_err(Error::WriteConfig)
 }
 
+/// Write the `[statesync]` section into the serialized `config.toml` so a
+/// fresh node can bootstrap from a recent snapshot instead of replaying every
+/// block. The ABCI app (Namada) must also advertise snapshots through its
+/// `ListSnapshots`/`OfferSnapshot` handlers for this to take effect.
+fn apply_statesync_config(
+    value: &mut toml::Value,
+    statesync: &config::StateSyncConfig,
+) -> Result<()> {
+    let toml::Value::Table(table) = value else {
+        return Ok(());
+    };
+    // CometBFT needs at least two RPC servers to cross-check the light-client
+    // header, so refuse to enable state sync without them.
+    if statesync.enable && statesync.rpc_servers.len() < 2 {
+        return Err(Error::StateSync(
+            "state sync requires at least two RPC servers for light-client \
+             verification"
+                .to_string(),
+        ));
+    }
+
+    let section = table
+        .entry("statesync".to_owned())
+        .or_insert_with(|| toml::Value::Table(Default::default()));
+    let toml::Value::Table(section) = section else {
+        return Ok(());
+    };
+    section.insert("enable".to_owned(), statesync.enable.into());
+    section.insert(
+        "rpc_servers".to_owned(),
+        toml::Value::Array(
+            statesync
+                .rpc_servers
+                .iter()
+                .cloned()
+                .map(toml::Value::String)
+                .collect(),
+        ),
+    );
+    section.insert(
+        "trust_height".to_owned(),
+        (statesync.trust_height as i64).into(),
+    );
+    section.insert(
+        "trust_hash".to_owned(),
+        statesync.trust_hash.clone().into(),
+    );
+    section.insert(
+        "trust_period".to_owned(),
+        statesync.trust_period.clone().into(),
+    );
+    Ok(())
+}
+
+/// Reconcile the serialized `config.toml` with the schema of the detected
+/// CometBFT version. The block-sync section was renamed from `[fastsync]`
+/// (Tendermint 0.34) to `[blocksync]` (CometBFT 0.37) and then dropped
+/// entirely in 0.38, where block sync is always on.
+fn apply_config_version_fixups(value: &mut toml::Value, version: CometbftVersion) {
+    let toml::Value::Table(table) = value else {
+        return;
+    };
+    match version {
+        CometbftVersion::V0_34 => {
+            if let Some(section) = table.remove("blocksync") {
+                table.insert("fastsync".to_owned(), section);
+            }
+        }
+        CometbftVersion::V0_37 => {
+            if let Some(section) = table.remove("fastsync") {
+                table.insert("blocksync".to_owned(), section);
+            }
+        }
+        CometbftVersion::V0_38 => {
+            table.remove("fastsync");
+            table.remove("blocksync");
+        }
+    }
+}
+
 async fn write_tm_genesis(
     home_dir: impl AsRef<Path>,
     chain_id: ChainId,
     genesis_time: DateTimeUtc,
+    version: CometbftVersion,
 ) {
     let home_dir = home_dir.as_ref();
     let path = home_dir.join("config").join("genesis.json");
@@ -455,9 +1065,163 @@ async fn write_tm_genesis(
                 path, err
             )
         });
+    // The typed `Genesis` always emits the 0.34-era consensus-params layout;
+    // reconcile it with the schema of the detected binary before writing.
+    let mut genesis = serde_json::to_value(&genesis)
+        .expect("Couldn't encode the CometBFT genesis file");
+    apply_genesis_version_fixups(&mut genesis, version);
     let data = serde_json::to_vec_pretty(&genesis)
         .expect("Couldn't encode the CometBFT genesis file");
     file.write_all(&data[..])
         .await
         .expect("Couldn't write the CometBFT genesis file");
 }
+
+/// Reconcile the generated `genesis.json` consensus params with the schema of
+/// the detected CometBFT version. `time_iota_ms` no longer has any meaning
+/// from 0.37 onwards, and 0.38 expects an `abci` sub-section advertising the
+/// `vote_extensions_enable_height`.
+fn apply_genesis_version_fixups(
+    genesis: &mut serde_json::Value,
+    version: CometbftVersion,
+) {
+    let Some(params) = genesis.get_mut("consensus_params") else {
+        return;
+    };
+    if !matches!(version, CometbftVersion::V0_34) {
+        if let Some(block) = params.get_mut("block").and_then(|b| b.as_object_mut())
+        {
+            block.remove("time_iota_ms");
+        }
+    }
+    if matches!(version, CometbftVersion::V0_38) {
+        if let Some(params) = params.as_object_mut() {
+            params
+                .entry("abci")
+                .or_insert_with(|| json!({ "vote_extensions_enable_height": "0" }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leading_u64() {
+        assert_eq!(leading_u64(Some("34")), Some(34));
+        // Tolerates pre-release suffixes on a version component.
+        assert_eq!(leading_u64(Some("38-rc1")), Some(38));
+        assert_eq!(leading_u64(Some("")), None);
+        assert_eq!(leading_u64(None), None);
+    }
+
+    #[test]
+    fn test_cometbft_version_from_semver() {
+        assert_eq!(
+            CometbftVersion::from_semver("0.34.2").unwrap(),
+            CometbftVersion::V0_34
+        );
+        assert_eq!(
+            CometbftVersion::from_semver("v0.37.0").unwrap(),
+            CometbftVersion::V0_37
+        );
+        // Ignores surrounding banner text and picks the semver token.
+        assert_eq!(
+            CometbftVersion::from_semver("CometBFT version 0.38.1").unwrap(),
+            CometbftVersion::V0_38
+        );
+        // Out-of-range and unparseable inputs error rather than guess.
+        assert!(CometbftVersion::from_semver("0.40.0").is_err());
+        assert!(CometbftVersion::from_semver("not a version").is_err());
+    }
+
+    #[test]
+    fn test_parse_rollback_output() {
+        let out = parse_rollback_output(
+            "Rolled back state to height 1234 and hash 0A1B2C",
+        )
+        .expect("should parse");
+        assert_eq!(out.height, BlockHeight(1234));
+        assert_eq!(out.app_hash, "0A1B2C");
+
+        // An unrecognized message parses to `None` so the caller can fall
+        // back rather than trusting a bad height.
+        assert!(parse_rollback_output("reverted to 1234").is_none());
+    }
+
+    #[test]
+    fn test_rpc_to_ws_url() {
+        assert_eq!(
+            rpc_to_ws_url("tcp://0.0.0.0:26657"),
+            "ws://127.0.0.1:26657/websocket"
+        );
+        assert_eq!(
+            rpc_to_ws_url("http://127.0.0.1:26657/"),
+            "ws://127.0.0.1:26657/websocket"
+        );
+    }
+
+    #[test]
+    fn test_scan_block_store_heights() {
+        let blob = b"garbage\x00{\"base\":1,\"height\":42}more\
+                     \x00{\"base\":2,\"height\":43}";
+        assert_eq!(scan_block_store_heights(blob), vec![42, 43]);
+        assert_eq!(scan_block_store_heights(b"no json here"), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_apply_config_version_fixups() {
+        let blocksync_table = || {
+            let mut table = toml::map::Map::new();
+            table.insert(
+                "fastsync".to_owned(),
+                toml::Value::Table(Default::default()),
+            );
+            toml::Value::Table(table)
+        };
+
+        // 0.37 renames `[fastsync]` to `[blocksync]`.
+        let mut value = blocksync_table();
+        apply_config_version_fixups(&mut value, CometbftVersion::V0_37);
+        assert!(value.get("blocksync").is_some());
+        assert!(value.get("fastsync").is_none());
+
+        // 0.38 drops the section entirely.
+        let mut value = blocksync_table();
+        apply_config_version_fixups(&mut value, CometbftVersion::V0_38);
+        assert!(value.get("blocksync").is_none());
+        assert!(value.get("fastsync").is_none());
+
+        // 0.34 keeps the `[fastsync]` spelling.
+        let mut value = blocksync_table();
+        apply_config_version_fixups(&mut value, CometbftVersion::V0_34);
+        assert!(value.get("fastsync").is_some());
+    }
+
+    #[test]
+    fn test_apply_genesis_version_fixups() {
+        let genesis = || {
+            json!({
+                "consensus_params": {
+                    "block": { "max_bytes": "1", "time_iota_ms": "1000" }
+                }
+            })
+        };
+
+        // 0.34 keeps `time_iota_ms`.
+        let mut g = genesis();
+        apply_genesis_version_fixups(&mut g, CometbftVersion::V0_34);
+        assert!(g["consensus_params"]["block"].get("time_iota_ms").is_some());
+
+        // 0.37 drops the defunct `time_iota_ms`.
+        let mut g = genesis();
+        apply_genesis_version_fixups(&mut g, CometbftVersion::V0_37);
+        assert!(g["consensus_params"]["block"].get("time_iota_ms").is_none());
+
+        // 0.38 additionally adds the `abci` sub-section.
+        let mut g = genesis();
+        apply_genesis_version_fixups(&mut g, CometbftVersion::V0_38);
+        assert!(g["consensus_params"].get("abci").is_some());
+    }
+}